@@ -0,0 +1,15 @@
+use proc_macro::TokenStream;
+
+mod measure_opts;
+mod metered;
+mod metered_opts;
+
+/// Generates a per-function metrics registry for an `impl` block and
+/// instruments every method carrying a `#[measure(...)]` attribute.
+#[proc_macro_attribute]
+pub fn metered(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    match metered::metered(attrs, item) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,188 @@
+//! Parses `#[measure(Kind { key = value, ... })]` attributes on functions
+//! inside a `#[metered(...)]` impl block into concrete metric field
+//! declarations for the generated per-function registry.
+
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, LitInt, LitStr, Token};
+
+/// One metric requested by a `#[measure(...)]` attribute, e.g. `ResponseTime`
+/// or `HitCount { ... }`.
+pub struct MetricRequest {
+    pub field_name: String,
+    kind: String,
+    low: Option<u64>,
+    high: Option<u64>,
+    sigfig: Option<u8>,
+    unit: Option<Ident>,
+}
+
+impl MetricRequest {
+    /// The registry field name this metric is stored under.
+    pub fn ident(&self) -> Ident {
+        Ident::new(&self.field_name, Span::call_site())
+    }
+
+    /// The bare Rust type backing this metric, ignoring any declared unit.
+    fn bare_type_path(&self) -> syn::Type {
+        match self.kind.as_str() {
+            "ResponseTime" | "Throughput" => {
+                syn::parse_quote!(metered::hdr_histogram::AtomicHdrHistogram)
+            }
+            "HitCount" | "ErrorCount" => syn::parse_quote!(metered::metric::Counter),
+            other => panic!("metered: unknown metric kind `{}`", other),
+        }
+    }
+
+    /// The Rust type used to store this metric in the generated registry.
+    ///
+    /// When a `unit` was declared on the `measure` attribute, the bare
+    /// metric type is wrapped in `metered::units::WithUnit<..>` so the unit
+    /// is carried into both the `serde` and Prometheus output.
+    pub fn type_path(&self) -> syn::Type {
+        let bare = self.bare_type_path();
+        match &self.unit {
+            None => bare,
+            Some(_) => syn::parse_quote!(metered::units::WithUnit<#bare>),
+        }
+    }
+
+    /// The expression used to initialize this metric's field in the
+    /// generated registry's `Default` impl.
+    ///
+    /// Metrics declared with explicit `low`/`high`/`sigfig` bounds construct
+    /// themselves via `HdrHistogram::with_bounds`/`AtomicHdrHistogram::with_bounds`
+    /// instead of `Default::default()`.
+    pub fn field_init(&self) -> proc_macro2::TokenStream {
+        let bare = self.bare_type_path();
+
+        let inner = match (self.low, self.high, self.sigfig) {
+            (None, None, None) => {
+                quote::quote! { <#bare as ::std::default::Default>::default() }
+            }
+            (low, high, sigfig) => {
+                let low = low.unwrap_or(1);
+                let high = high.unwrap_or(5 * 60 * 1000);
+                let sigfig = sigfig.unwrap_or(2);
+                quote::quote! { #bare::with_bounds(#low, #high, #sigfig) }
+            }
+        };
+
+        match &self.unit {
+            None => inner,
+            Some(unit) => quote::quote! {
+                metered::units::WithUnit::new(#inner, metered::units::Unit::#unit)
+            },
+        }
+    }
+}
+
+impl Parse for MetricRequest {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kind: Ident = input.parse()?;
+        let kind_str = kind.to_string();
+
+        let mut low = None;
+        let mut high = None;
+        let mut sigfig = None;
+        let mut unit = None;
+
+        if input.peek(syn::token::Brace) {
+            let content;
+            syn::braced!(content in input);
+            let opts: Punctuated<MetricOption, Token![,]> =
+                content.parse_terminated(MetricOption::parse)?;
+
+            for opt in opts {
+                match opt {
+                    MetricOption::Low(v) => low = Some(v),
+                    MetricOption::High(v) => high = Some(v),
+                    MetricOption::Sigfig(v) => sigfig = Some(v),
+                    MetricOption::Unit(v) => unit = Some(v),
+                }
+            }
+        }
+
+        use heck::SnakeCase;
+        Ok(MetricRequest {
+            field_name: kind_str.to_snake_case(),
+            kind: kind_str,
+            low,
+            high,
+            sigfig,
+            unit,
+        })
+    }
+}
+
+enum MetricOption {
+    Low(u64),
+    High(u64),
+    Sigfig(u8),
+    Unit(Ident),
+}
+
+/// Maps a `unit = "..."` string literal to the matching
+/// `metered::units::Unit` variant name.
+fn unit_variant(lit: &LitStr) -> syn::Result<Ident> {
+    let variant = match lit.value().as_str() {
+        "seconds" => "Seconds",
+        "milliseconds" => "Milliseconds",
+        "microseconds" => "Microseconds",
+        "bytes" => "Bytes",
+        "count" => "Count",
+        other => {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!("metered: unknown unit `{}`", other),
+            ))
+        }
+    };
+    Ok(Ident::new(variant, lit.span()))
+}
+
+impl Parse for MetricOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        match key.to_string().as_str() {
+            "low" => Ok(MetricOption::Low(input.parse::<LitInt>()?.base10_parse()?)),
+            "high" => Ok(MetricOption::High(input.parse::<LitInt>()?.base10_parse()?)),
+            "sigfig" => Ok(MetricOption::Sigfig(
+                input.parse::<LitInt>()?.base10_parse()?,
+            )),
+            "unit" => Ok(MetricOption::Unit(unit_variant(&input.parse::<LitStr>()?)?)),
+            other => Err(syn::Error::new(
+                key.span(),
+                format!("metered: unknown measure option `{}`", other),
+            )),
+        }
+    }
+}
+
+/// A single `#[measure(...)]` attribute, which may request one or more
+/// metrics: `#[measure(ResponseTime, HitCount)]`.
+pub struct MeasureRequestAttribute {
+    requests: Vec<MetricRequest>,
+}
+
+impl MeasureRequestAttribute {
+    pub fn to_requests(&self) -> &[MetricRequest] {
+        &self.requests
+    }
+}
+
+impl Parse for MeasureRequestAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `synattra` hands us the attribute's tokens verbatim, i.e. still
+        // wrapped in the `(...)` delimiter from `#[measure(...)]`.
+        let content;
+        syn::parenthesized!(content in input);
+        let requests = Punctuated::<MetricRequest, Token![,]>::parse_terminated(&content)?;
+        Ok(MeasureRequestAttribute {
+            requests: requests.into_iter().collect(),
+        })
+    }
+}
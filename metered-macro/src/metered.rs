@@ -4,6 +4,7 @@ use crate::measure_opts::MeasureRequestAttribute;
 use crate::metered_opts::MeteredKeyValAttribute;
 
 use aspect_weave::*;
+use quote::quote;
 use std::rc::Rc;
 use synattra::ParseAttributes;
 
@@ -19,6 +20,8 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
     let mut code = quote! {};
 
     let mut reg_fields = quote! {};
+    let mut reg_prometheus = quote! {};
+    let mut reg_clear = quote! {};
     for (fun_name, _) in measured.iter() {
         use heck::CamelCase;
         let fun_reg_name = format!("{}{}", registry_name, fun_name.to_string().to_camel_case());
@@ -27,7 +30,21 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
         reg_fields = quote! {
             #reg_fields
             #fun_name : #fun_registry_ident,
-        }
+        };
+
+        let fun_name_str = fun_name.to_string();
+        reg_prometheus = quote! {
+            #reg_prometheus
+            self.#fun_name.write_prometheus(
+                &format!("{}_{}", #registry_name, #fun_name_str),
+                &mut out,
+            );
+        };
+
+        reg_clear = quote! {
+            #reg_clear
+            metered::clear::Clear::clear(&self.#fun_name);
+        };
     }
 
     code = quote! {
@@ -37,6 +54,23 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
         struct #registry_ident {
             #reg_fields
         }
+
+        impl #registry_ident {
+            /// Renders every metric in this registry in the Prometheus text
+            /// exposition format.
+            pub fn to_prometheus(&self) -> String {
+                use metered::prometheus::PrometheusMetric;
+                let mut out = String::new();
+                #reg_prometheus
+                out
+            }
+        }
+
+        impl metered::clear::Clear for #registry_ident {
+            fn clear(&self) {
+                #reg_clear
+            }
+        }
     };
 
     drop(reg_fields);
@@ -47,6 +81,9 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
         let fun_registry_ident = syn::Ident::new(&fun_reg_name, impl_block.impl_token.span);
 
         let mut fun_reg_fields = quote! {};
+        let mut fun_reg_field_inits = quote! {};
+        let mut fun_reg_prometheus = quote! {};
+        let mut fun_reg_clear = quote! {};
 
         for measure_req_attr in measure_request_attrs.iter() {
             let metric_requests = measure_req_attr.to_requests();
@@ -54,21 +91,73 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
             for metric in metric_requests.iter() {
                 let metric_field = metric.ident();
                 let metric_type = metric.type_path();
+                let metric_field_str = metric_field.to_string();
+                // Metrics declared with explicit bounds (e.g.
+                // `#[measure(ResponseTime { low = ..., high = ..., sigfig = ... })]`)
+                // construct themselves via their own constructor instead of
+                // `Default::default()`.
+                let metric_field_init = metric.field_init();
 
                 fun_reg_fields = quote! {
                     #fun_reg_fields
                     #metric_field : #metric_type,
-                }
+                };
+
+                fun_reg_field_inits = quote! {
+                    #fun_reg_field_inits
+                    #metric_field : #metric_field_init,
+                };
+
+                fun_reg_prometheus = quote! {
+                    #fun_reg_prometheus
+                    {
+                        let name = format!("{}_{}", prefix, #metric_field_str);
+                        let _ = writeln!(
+                            out,
+                            "# TYPE {} {}",
+                            name,
+                            <#metric_type as PrometheusMetric>::prometheus_type()
+                        );
+                        self.#metric_field.write_prometheus(&name, out);
+                    }
+                };
+
+                fun_reg_clear = quote! {
+                    #fun_reg_clear
+                    metered::clear::Clear::clear(&self.#metric_field);
+                };
             }
         }
 
         code = quote! {
             #code
 
-            #[derive(Debug, Default, serde::Serialize)]
+            #[derive(Debug, serde::Serialize)]
             struct #fun_registry_ident {
                 #fun_reg_fields
             }
+
+            impl Default for #fun_registry_ident {
+                fn default() -> Self {
+                    #fun_registry_ident {
+                        #fun_reg_field_inits
+                    }
+                }
+            }
+
+            impl #fun_registry_ident {
+                fn write_prometheus(&self, prefix: &str, out: &mut String) {
+                    use metered::prometheus::PrometheusMetric;
+                    use std::fmt::Write;
+                    #fun_reg_prometheus
+                }
+            }
+
+            impl metered::clear::Clear for #fun_registry_ident {
+                fn clear(&self) {
+                    #fun_reg_clear
+                }
+            }
         };
     }
 
@@ -78,9 +167,7 @@ pub fn metered(attrs: TokenStream, item: TokenStream) -> syn::Result<TokenStream
         #code
     };
 
-    let result: TokenStream = code.into();
-    println!("Result {}", result.to_string());
-    Ok(result)
+    Ok(code.into())
 }
 
 struct MeteredWeave;
@@ -98,11 +185,10 @@ impl Weave for MeteredWeave {
 
         let r: proc_macro::TokenStream = measure_list(
             &metered.registry_expr,
-            &ident,
+            ident,
             fn_attr,
             quote! { #block }.into(),
-        )
-        .into();
+        );
 
         let new_block: syn::Block = syn::parse(r).expect("block");
         Ok(new_block)
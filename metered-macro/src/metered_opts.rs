@@ -0,0 +1,85 @@
+//! Parses the `#[metered(registry = ..., registry_expr = ...)]` key/value
+//! attribute placed on the `impl` block itself, naming the generated
+//! registry type and where to find an instance of it on `self`.
+
+use heck::SnakeCase;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, Token};
+
+/// The resolved `#[metered(...)]` configuration for a single `impl` block.
+pub struct Metered {
+    pub registry_name: String,
+    pub registry_ident: Ident,
+    pub registry_expr: Expr,
+}
+
+/// The raw `#[metered(...)]` attribute, before defaults are applied.
+pub struct MeteredKeyValAttribute {
+    registry: Ident,
+    registry_expr: Option<Expr>,
+}
+
+impl MeteredKeyValAttribute {
+    /// Resolves defaults into a [`Metered`]; `registry_expr` falls back to
+    /// `self.<snake_case(registry)>` when not given explicitly.
+    pub fn to_metered(&self) -> Metered {
+        let registry_expr = self.registry_expr.clone().unwrap_or_else(|| {
+            let field = Ident::new(
+                &self.registry.to_string().to_snake_case(),
+                self.registry.span(),
+            );
+            syn::parse_quote! { self.#field }
+        });
+
+        Metered {
+            registry_name: self.registry.to_string(),
+            registry_ident: self.registry.clone(),
+            registry_expr,
+        }
+    }
+}
+
+enum MeteredOption {
+    Registry(Ident),
+    RegistryExpr(Box<Expr>),
+}
+
+impl Parse for MeteredOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        match key.to_string().as_str() {
+            "registry" => Ok(MeteredOption::Registry(input.parse()?)),
+            "registry_expr" => Ok(MeteredOption::RegistryExpr(Box::new(input.parse()?))),
+            other => Err(syn::Error::new(
+                key.span(),
+                format!("metered: unknown option `{}`", other),
+            )),
+        }
+    }
+}
+
+impl Parse for MeteredKeyValAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let opts: Punctuated<MeteredOption, Token![,]> = Punctuated::parse_terminated(input)?;
+
+        let mut registry = None;
+        let mut registry_expr = None;
+        for opt in opts {
+            match opt {
+                MeteredOption::Registry(v) => registry = Some(v),
+                MeteredOption::RegistryExpr(v) => registry_expr = Some(*v),
+            }
+        }
+
+        let registry =
+            registry.ok_or_else(|| syn::Error::new(proc_macro2::Span::call_site(), "metered: missing `registry = ...`"))?;
+
+        Ok(MeteredKeyValAttribute {
+            registry,
+            registry_expr,
+        })
+    }
+}
@@ -0,0 +1,272 @@
+use crate::clear::Clear;
+use crate::metric::Histogram;
+use crate::prometheus::PrometheusMetric;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of linear sub-buckets per power-of-two magnitude, matching the
+/// granularity `HdrHistogram`'s index scheme gets from its significant
+/// figures.
+const SUB_BUCKETS: usize = 32;
+const SUB_BUCKET_BITS: u32 = 5; // log2(SUB_BUCKETS)
+const MAGNITUDES: usize = 64;
+const BUCKET_COUNT: usize = MAGNITUDES * SUB_BUCKETS;
+
+fn bucket_index(value: u64) -> usize {
+    if value == 0 {
+        return 0;
+    }
+    let magnitude = 63 - value.leading_zeros();
+    let shift = magnitude.saturating_sub(SUB_BUCKET_BITS);
+    let sub_bucket = ((value >> shift) as usize) & (SUB_BUCKETS - 1);
+    ((magnitude as usize) * SUB_BUCKETS + sub_bucket).min(BUCKET_COUNT - 1)
+}
+
+fn bucket_representative_value(index: usize) -> u64 {
+    let magnitude = (index / SUB_BUCKETS) as u32;
+    let sub_bucket = (index % SUB_BUCKETS) as u64;
+    let shift = magnitude.saturating_sub(SUB_BUCKET_BITS);
+    // Below `SUB_BUCKETS` every value has its own bucket; above it the
+    // sub-bucket holds the bits after the implicit leading one.
+    let bucket_start = if magnitude < SUB_BUCKET_BITS {
+        sub_bucket
+    } else {
+        ((SUB_BUCKETS as u64) + sub_bucket) << shift
+    };
+    // Representative value is the midpoint of the bucket's range.
+    bucket_start + ((1u64 << shift) >> 1)
+}
+
+fn atomic_fetch_min(atomic: &AtomicU64, value: u64) {
+    let mut current = atomic.load(Ordering::Relaxed);
+    while value < current {
+        match atomic.compare_exchange_weak(current, value, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(prev) => current = prev,
+        }
+    }
+}
+
+fn atomic_fetch_max(atomic: &AtomicU64, value: u64) {
+    let mut current = atomic.load(Ordering::Relaxed);
+    while value > current {
+        match atomic.compare_exchange_weak(current, value, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(prev) => current = prev,
+        }
+    }
+}
+
+/// A histogram that records without ever taking a lock.
+///
+/// Unlike [`AtomicHdrHistogram`](crate::hdr_histogram::AtomicHdrHistogram), which
+/// serializes every recording thread through an `AtomicRefCell` borrow,
+/// `AtomicHistogram` precomputes a fixed logarithmic bucket layout into a
+/// `Box<[AtomicU64]>` and counts each value into its bucket with a single
+/// `fetch_add(1, Relaxed)` (plus atomic updates of `min`/`max`/`sum`/`count`),
+/// trading a small amount of percentile precision for lock-free recording
+/// under many concurrent writers.
+pub struct AtomicHistogram {
+    low: u64,
+    high: u64,
+    buckets: Box<[AtomicU64]>,
+    min: AtomicU64,
+    max: AtomicU64,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+impl AtomicHistogram {
+    pub fn with_bounds(low: u64, high: u64) -> Self {
+        let buckets = (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect();
+        AtomicHistogram {
+            low,
+            high,
+            buckets,
+            min: AtomicU64::new(u64::MAX),
+            max: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// Snapshot of `(count, min, max, sum, mean)` taken with independent
+    /// `Relaxed` loads; fine for reporting, not meant to be exact under
+    /// concurrent writers.
+    fn stats(&self) -> (u64, u64, u64, u64, f64) {
+        let count = self.count.load(Ordering::Relaxed);
+        let min = if count == 0 {
+            0
+        } else {
+            self.min.load(Ordering::Relaxed)
+        };
+        let max = self.max.load(Ordering::Relaxed);
+        let sum = self.sum.load(Ordering::Relaxed);
+        let mean = if count == 0 {
+            0.0
+        } else {
+            sum as f64 / count as f64
+        };
+        (count, min, max, sum, mean)
+    }
+
+    /// Computes the value at percentile `p` (0.0-1.0) from a fresh snapshot
+    /// of the bucket counts.
+    fn percentile(&self, p: f64) -> u64 {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+
+        let target = (p * total as f64).ceil() as u64;
+        let mut running = 0u64;
+        for (index, bucket_count) in counts.iter().enumerate() {
+            running += bucket_count;
+            if running >= target {
+                return bucket_representative_value(index);
+            }
+        }
+        self.max.load(Ordering::Relaxed)
+    }
+}
+
+const PERCENTILES: &[(&str, f64)] = &[
+    ("90%ile", 0.90),
+    ("95%ile", 0.95),
+    ("99%ile", 0.99),
+    ("99.9ile", 0.999),
+    ("99.99ile", 0.9999),
+];
+
+impl Histogram for AtomicHistogram {
+    fn record(&self, value: u64) {
+        // Saturating semantics: values outside [low, high] clamp to the
+        // nearest bound rather than being dropped or panicking.
+        let clamped = value.clamp(self.low, self.high);
+        let index = bucket_index(clamped);
+
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(clamped, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        atomic_fetch_min(&self.min, clamped);
+        atomic_fetch_max(&self.max, clamped);
+    }
+}
+
+impl Serialize for AtomicHistogram {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (count, min, max, _sum, mean) = self.stats();
+
+        let mut map = serializer.serialize_map(Some(4 + PERCENTILES.len()))?;
+        map.serialize_entry("samples", &count)?;
+        map.serialize_entry("min", &min)?;
+        map.serialize_entry("max", &max)?;
+        map.serialize_entry("mean", &mean)?;
+        for (label, p) in PERCENTILES {
+            map.serialize_entry(label, &self.percentile(*p))?;
+        }
+        map.end()
+    }
+}
+
+impl PrometheusMetric for AtomicHistogram {
+    fn prometheus_type() -> &'static str {
+        "summary"
+    }
+
+    fn write_prometheus(&self, name: &str, out: &mut String) {
+        use std::fmt::Write;
+
+        for (_, p) in PERCENTILES {
+            let _ = writeln!(out, "{}{{quantile=\"{}\"}} {}", name, p, self.percentile(*p));
+        }
+        let (count, _min, _max, sum, _mean) = self.stats();
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+impl Clear for AtomicHistogram {
+    fn clear(&self) {
+        for bucket in self.buckets.iter() {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.min.store(u64::MAX, Ordering::Relaxed);
+        self.max.store(0, Ordering::Relaxed);
+        self.sum.store(0, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+    }
+}
+
+use std::fmt;
+use std::fmt::Debug;
+impl Debug for AtomicHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AtomicHistogram {{ samples: {}, min: {}, max: {} }}",
+            self.count.load(Ordering::Relaxed),
+            self.min.load(Ordering::Relaxed),
+            self.max.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl Default for AtomicHistogram {
+    fn default() -> Self {
+        // Matches `HdrHistogram`'s default range: 1ms to 5 minutes.
+        Self::with_bounds(1, 5 * 60 * 1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_histogram_reports_zero_without_panicking() {
+        let histo = AtomicHistogram::with_bounds(1, 5 * 60 * 1000);
+
+        assert_eq!(histo.percentile(0.50), 0);
+        assert_eq!(histo.percentile(0.99), 0);
+        let (count, min, max, sum, mean) = histo.stats();
+        assert_eq!((count, min, max, sum, mean), (0, 0, 0, 0, 0.0));
+    }
+
+    #[test]
+    fn percentiles_are_monotonic_and_bounded() {
+        let histo = AtomicHistogram::with_bounds(1, 5 * 60 * 1000);
+        for value in 1..=1000u64 {
+            histo.record(value);
+        }
+
+        let p50 = histo.percentile(0.50);
+        let p90 = histo.percentile(0.90);
+        let p99 = histo.percentile(0.99);
+
+        assert!(p50 <= p90);
+        assert!(p90 <= p99);
+        assert!(p99 <= 1000);
+    }
+
+    #[test]
+    fn values_outside_bounds_saturate_instead_of_panicking() {
+        let histo = AtomicHistogram::with_bounds(10, 100);
+        histo.record(0);
+        histo.record(1_000_000);
+
+        let (count, min, max, ..) = histo.stats();
+        assert_eq!(count, 2);
+        assert_eq!(min, 10);
+        assert_eq!(max, 100);
+    }
+}
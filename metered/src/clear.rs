@@ -0,0 +1,5 @@
+/// Implemented by metrics and registries that can reset their accumulated
+/// state at runtime, e.g. to snapshot-and-reset on every reporting interval.
+pub trait Clear {
+    fn clear(&self);
+}
@@ -1,4 +1,6 @@
+use crate::clear::Clear;
 use crate::metric::Histogram;
+use crate::prometheus::PrometheusMetric;
 use serde::{Serialize, Serializer};
 
 use atomic_refcell::AtomicRefCell;
@@ -8,6 +10,16 @@ pub struct AtomicHdrHistogram {
     inner: AtomicRefCell<HdrHistogram>,
 }
 
+impl AtomicHdrHistogram {
+    /// Builds a histogram covering `[low, high]` with `sigfig` significant
+    /// figures of resolution, per the `hdrhistogram` crate's conventions.
+    pub fn with_bounds(low: u64, high: u64, sigfig: u8) -> Self {
+        AtomicHdrHistogram {
+            inner: AtomicRefCell::new(HdrHistogram::with_bounds(low, high, sigfig)),
+        }
+    }
+}
+
 impl Histogram for AtomicHdrHistogram {
     fn record(&self, value: u64) {
         self.inner.borrow_mut().record(value);
@@ -26,6 +38,22 @@ impl Serialize for AtomicHdrHistogram {
     }
 }
 
+impl PrometheusMetric for AtomicHdrHistogram {
+    fn prometheus_type() -> &'static str {
+        HdrHistogram::prometheus_type()
+    }
+
+    fn write_prometheus(&self, name: &str, out: &mut String) {
+        self.inner.borrow().write_prometheus(name, out);
+    }
+}
+
+impl Clear for AtomicHdrHistogram {
+    fn clear(&self) {
+        self.inner.borrow_mut().clear();
+    }
+}
+
 use std::fmt;
 use std::fmt::Debug;
 impl Debug for AtomicHdrHistogram {
@@ -37,13 +65,27 @@ impl Debug for AtomicHdrHistogram {
 
 pub struct HdrHistogram {
     histo: hdrhistogram::Histogram<u64>,
+    high: u64,
 }
 
 impl HdrHistogram {
+    /// Builds a histogram covering `[low, high]` with `sigfig` significant
+    /// figures of resolution, e.g. for `#[measure(ResponseTime { low = 1, high = 3_600_000, sigfig = 3 })]`.
+    pub fn with_bounds(low: u64, high: u64, sigfig: u8) -> Self {
+        let histo = hdrhistogram::Histogram::<u64>::new_with_bounds(low, high, sigfig)
+            .expect("Could not instantiate HdrHistogram");
+
+        HdrHistogram { histo, high }
+    }
+
     fn record(&mut self, value: u64) {
-        // All recordings will be saturating, that is, a value higher than 5 minutes
-        // will be replace by 5 minutes...
-        self.histo.saturating_record(value);
+        // All recordings will be saturating, that is, a value higher than
+        // `high` will be replaced by `high`...
+        self.histo.saturating_record(value.min(self.high));
+    }
+
+    fn clear(&mut self) {
+        self.histo.clear();
     }
 }
 
@@ -72,6 +114,37 @@ impl Serialize for HdrHistogram {
     }
 }
 
+impl PrometheusMetric for HdrHistogram {
+    fn prometheus_type() -> &'static str {
+        "summary"
+    }
+
+    fn write_prometheus(&self, name: &str, out: &mut String) {
+        use std::fmt::Write;
+
+        let hdr = &self.histo;
+        let ile = |v| hdr.value_at_percentile(v);
+
+        for (label, percentile) in &[
+            ("0.9", 90.0),
+            ("0.95", 95.0),
+            ("0.99", 99.0),
+            ("0.999", 99.9),
+            ("0.9999", 99.99),
+        ] {
+            let _ = writeln!(
+                out,
+                "{}{{quantile=\"{}\"}} {}",
+                name,
+                label,
+                ile(*percentile)
+            );
+        }
+        let _ = writeln!(out, "{}_sum {}", name, hdr.mean() * hdr.len() as f64);
+        let _ = writeln!(out, "{}_count {}", name, hdr.len());
+    }
+}
+
 impl Debug for HdrHistogram {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let hdr = &self.histo;
@@ -98,11 +171,31 @@ impl Debug for HdrHistogram {
 impl Default for HdrHistogram {
     fn default() -> Self {
         // A HdrHistogram measuring latencies from 1ms to 5minutes
-        // All recordings will be saturating, that is, a value higher than 5 minutes
-        // will be replace by 5 minutes...
-        let histo = hdrhistogram::Histogram::<u64>::new_with_bounds(1, 5 * 60 * 1000, 2)
-            .expect("Could not instantiate HdrHistogram");
+        Self::with_bounds(1, 5 * 60 * 1000, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_above_high_saturate_at_the_configured_bound() {
+        let mut histo = HdrHistogram::with_bounds(1, 100, 2);
+        histo.record(1_000_000);
+
+        assert_eq!(histo.histo.max(), 100);
+    }
+
+    #[test]
+    fn clear_zeroes_a_populated_histogram() {
+        let mut histo = HdrHistogram::with_bounds(1, 100, 2);
+        histo.record(10);
+        histo.record(20);
+        assert_eq!(histo.histo.len(), 2);
+
+        histo.clear();
 
-        HdrHistogram { histo }
+        assert_eq!(histo.histo.len(), 0);
     }
 }
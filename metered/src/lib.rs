@@ -0,0 +1,23 @@
+pub mod atomic_histogram;
+pub mod clear;
+pub mod hdr_histogram;
+pub mod metric;
+pub mod prometheus;
+#[cfg(feature = "tcp-exporter")]
+pub mod tcp_exporter;
+pub mod units;
+
+/// Generates a per-function metrics registry for an `impl` block and
+/// instruments every method carrying a `#[measure(...)]` attribute. See
+/// `metered-macro` for the attribute syntax.
+pub use metered_macro::metered;
+
+/// Wraps `$body` so its outcome is recorded into `$metric` before the result
+/// is returned. Emitted by the `#[metered(...)]` codegen for every
+/// `#[measure(...)]` attribute; not usually invoked by hand.
+#[macro_export]
+macro_rules! measure {
+    ($metric:expr, $body:expr) => {
+        $crate::metric::OnResult::on_result($metric, || $body)
+    };
+}
@@ -0,0 +1,95 @@
+use crate::clear::Clear;
+use crate::prometheus::PrometheusMetric;
+use serde::{Serialize, Serializer};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Implemented by any per-function metric that records a `u64` sample, e.g.
+/// a response time or a throughput count.
+pub trait Histogram {
+    fn record(&self, value: u64);
+}
+
+/// Implemented by any metric that can observe the outcome of a measured
+/// expression. Backs the `measure!` macro the `#[metered(...)]` codegen
+/// emits for every `#[measure(...)]` attribute; not usually called by hand.
+pub trait OnResult {
+    fn on_result<R>(&self, f: impl FnOnce() -> R) -> R;
+}
+
+/// Any histogram metric records the wall-clock duration of the measured
+/// expression, in milliseconds.
+impl<T: Histogram> OnResult for T {
+    fn on_result<R>(&self, f: impl FnOnce() -> R) -> R {
+        let start = std::time::Instant::now();
+        let result = f();
+        self.record(start.elapsed().as_millis() as u64);
+        result
+    }
+}
+
+/// A lock-free monotonic counter backing `HitCount` and `ErrorCount`
+/// metrics.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn incr(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Serialize for Counter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.get())
+    }
+}
+
+impl Clear for Counter {
+    fn clear(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+impl PrometheusMetric for Counter {
+    fn prometheus_type() -> &'static str {
+        "counter"
+    }
+
+    fn write_prometheus(&self, name: &str, out: &mut String) {
+        use std::fmt::Write;
+        let _ = writeln!(out, "{}_total {}", name, self.get());
+    }
+}
+
+/// A counter increments on every call, regardless of the expression's
+/// outcome.
+impl OnResult for Counter {
+    fn on_result<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.incr();
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_zeroes_a_populated_counter() {
+        let counter = Counter::default();
+        counter.incr();
+        counter.incr();
+        assert_eq!(counter.get(), 2);
+
+        counter.clear();
+
+        assert_eq!(counter.get(), 0);
+    }
+}
@@ -0,0 +1,13 @@
+/// Implemented by metric types that know how to render themselves in the
+/// Prometheus text exposition format.
+///
+/// The code generated by `#[metered(...)]` calls this for every metric field
+/// a registry holds, passing a fully qualified name (registry + function +
+/// field) so exported series never collide across functions.
+pub trait PrometheusMetric {
+    /// The Prometheus metric kind, used to emit the `# TYPE` line.
+    fn prometheus_type() -> &'static str;
+
+    /// Append this metric's samples to `out` under `name`.
+    fn write_prometheus(&self, name: &str, out: &mut String);
+}
@@ -0,0 +1,113 @@
+//! Streaming TCP export of periodic registry snapshots.
+//!
+//! Opt in with the `tcp-exporter` feature; it is not pulled in by default
+//! since most users only need the `serde`/Prometheus output produced
+//! on-demand by the generated registry methods.
+
+use serde::Serialize;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct Snapshot<'a, R> {
+    timestamp: u64,
+    registry: &'a R,
+}
+
+/// Periodically serializes `registry` and pushes a length-prefixed JSON
+/// frame to every connected collector, so callers don't have to poll for
+/// metrics themselves.
+///
+/// Takes `registry` as an `Arc` rather than by value: the caller keeps
+/// their own clone to keep recording into the same registry this exporter
+/// is reading snapshots from. Spawns a background thread listening on
+/// `addr`; every connected client receives one frame every `interval`,
+/// each prefixed with its length as a big-endian `u32`, until it
+/// disconnects.
+pub fn serve_tcp<R, A>(registry: Arc<R>, addr: A, interval: Duration)
+where
+    R: Serialize + Send + Sync + 'static,
+    A: ToSocketAddrs,
+{
+    let listener = TcpListener::bind(addr).expect("could not bind tcp exporter listener");
+    listener
+        .set_nonblocking(true)
+        .expect("could not set tcp exporter listener to non-blocking");
+
+    thread::spawn(move || {
+        let mut clients: Vec<TcpStream> = Vec::new();
+
+        loop {
+            while let Ok((stream, _)) = listener.accept() {
+                clients.push(stream);
+            }
+
+            if !clients.is_empty() {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+
+                let snapshot = Snapshot {
+                    timestamp,
+                    registry: registry.as_ref(),
+                };
+
+                if let Ok(payload) = serde_json::to_vec(&snapshot) {
+                    let len = (payload.len() as u32).to_be_bytes();
+                    let mut still_connected = Vec::with_capacity(clients.len());
+                    for mut client in clients.drain(..) {
+                        let sent = client
+                            .write_all(&len)
+                            .and_then(|_| client.write_all(&payload));
+                        if sent.is_ok() {
+                            still_connected.push(client);
+                        }
+                    }
+                    clients = still_connected;
+                }
+            }
+
+            thread::sleep(interval);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[derive(Serialize)]
+    struct FakeRegistry {
+        value: u64,
+    }
+
+    #[test]
+    fn frames_each_snapshot_with_a_big_endian_length_prefix() {
+        let registry = Arc::new(FakeRegistry { value: 42 });
+        serve_tcp(registry, "127.0.0.1:34871", Duration::from_millis(10));
+
+        let mut stream =
+            TcpStream::connect("127.0.0.1:34871").expect("could not connect to exporter");
+
+        let mut len_bytes = [0u8; 4];
+        stream
+            .read_exact(&mut len_bytes)
+            .expect("could not read length prefix");
+        let len = u32::from_be_bytes(len_bytes) as usize;
+
+        let mut payload = vec![0u8; len];
+        stream
+            .read_exact(&mut payload)
+            .expect("could not read framed payload");
+
+        let snapshot: serde_json::Value =
+            serde_json::from_slice(&payload).expect("payload was not valid JSON");
+        assert_eq!(snapshot["registry"]["value"], 42);
+        assert!(snapshot["timestamp"].is_u64());
+    }
+}
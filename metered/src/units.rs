@@ -0,0 +1,159 @@
+use crate::clear::Clear;
+use crate::metric::{Counter, Histogram, OnResult};
+use crate::prometheus::PrometheusMetric;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+/// The physical unit a measured metric represents, declared via e.g.
+/// `#[measure(ResponseTime { unit = "milliseconds" })]`. Carried through to
+/// both the `serde` output and Prometheus export so dashboards can label
+/// axes correctly.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    #[default]
+    Unspecified,
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Bytes,
+    Count,
+}
+
+impl Unit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Unspecified => "unspecified",
+            Unit::Seconds => "seconds",
+            Unit::Milliseconds => "milliseconds",
+            Unit::Microseconds => "microseconds",
+            Unit::Bytes => "bytes",
+            Unit::Count => "count",
+        }
+    }
+}
+
+/// Parse error returned when a `unit = "..."` string literal names an
+/// unrecognized unit.
+#[derive(Debug)]
+pub struct ParseUnitError;
+
+impl std::str::FromStr for Unit {
+    type Err = ParseUnitError;
+
+    /// Parses the `unit = "..."` string literal accepted by `#[measure(...)]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "seconds" => Unit::Seconds,
+            "milliseconds" => Unit::Milliseconds,
+            "microseconds" => Unit::Microseconds,
+            "bytes" => Unit::Bytes,
+            "count" => Unit::Count,
+            _ => return Err(ParseUnitError),
+        })
+    }
+}
+
+/// Wraps a metric together with the [`Unit`] it was declared with.
+///
+/// Forwards recording, clearing and Prometheus export to the inner metric,
+/// adding a `unit` entry to its serialized form. The macro only reaches for
+/// this wrapper when a `unit` is declared on a `measure` attribute; metrics
+/// left without one keep their bare, unwrapped field type, so existing code
+/// is unaffected.
+#[derive(Debug, Default)]
+pub struct WithUnit<M> {
+    metric: M,
+    unit: Unit,
+}
+
+impl<M> WithUnit<M> {
+    pub fn new(metric: M, unit: Unit) -> Self {
+        WithUnit { metric, unit }
+    }
+}
+
+impl<M: Histogram> Histogram for WithUnit<M> {
+    fn record(&self, value: u64) {
+        self.metric.record(value);
+    }
+}
+
+/// `Counter` isn't a [`Histogram`], so it needs its own passthrough: unit
+/// wrapping a `HitCount`/`ErrorCount` (e.g. `#[measure(HitCount { unit =
+/// "count" })]`) would otherwise leave `.incr()`/`.get()` unreachable.
+impl WithUnit<Counter> {
+    pub fn incr(&self) {
+        self.metric.incr();
+    }
+
+    pub fn get(&self) -> u64 {
+        self.metric.get()
+    }
+}
+
+impl OnResult for WithUnit<Counter> {
+    fn on_result<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.incr();
+        f()
+    }
+}
+
+impl<M: Clear> Clear for WithUnit<M> {
+    fn clear(&self) {
+        self.metric.clear();
+    }
+}
+
+impl<M: Serialize> Serialize for WithUnit<M> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("value", &self.metric)?;
+        map.serialize_entry("unit", self.unit.as_str())?;
+        map.end()
+    }
+}
+
+impl<M: PrometheusMetric> PrometheusMetric for WithUnit<M> {
+    fn prometheus_type() -> &'static str {
+        M::prometheus_type()
+    }
+
+    fn write_prometheus(&self, name: &str, out: &mut String) {
+        use std::fmt::Write;
+
+        if self.unit != Unit::Unspecified {
+            let _ = writeln!(out, "# UNIT {} {}", name, self.unit.as_str());
+        }
+        self.metric.write_prometheus(name, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_as_a_value_unit_pair() {
+        let with_unit = WithUnit::new(Counter::default(), Unit::Count);
+        with_unit.incr();
+
+        let json = serde_json::to_value(&with_unit).unwrap();
+        assert_eq!(json, serde_json::json!({ "value": 1, "unit": "count" }));
+    }
+
+    #[test]
+    fn write_prometheus_emits_a_unit_line_only_when_a_unit_was_declared() {
+        let with_unit = WithUnit::new(Counter::default(), Unit::Count);
+        let mut out = String::new();
+        with_unit.write_prometheus("my_metric", &mut out);
+        assert!(out.contains("# UNIT my_metric count"));
+
+        let without_unit = WithUnit::new(Counter::default(), Unit::Unspecified);
+        let mut out = String::new();
+        without_unit.write_prometheus("my_metric", &mut out);
+        assert!(!out.contains("# UNIT"));
+    }
+}
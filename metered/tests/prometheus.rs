@@ -0,0 +1,33 @@
+//! Exercises `#[metered(...)]`/`#[measure(...)]` end-to-end and pins the
+//! exact Prometheus text exposition output, including the `# TYPE` lines
+//! that chunk0-1's first pass silently omitted.
+
+use metered::metered;
+
+#[derive(Default)]
+struct Service {
+    service_metrics: ServiceMetrics,
+}
+
+#[metered(registry = ServiceMetrics)]
+impl Service {
+    #[measure(HitCount)]
+    fn process(&self) {}
+
+    #[measure(ErrorCount)]
+    fn fail(&self) {}
+}
+
+#[test]
+fn to_prometheus_emits_a_type_line_for_every_metric() {
+    let service = Service::default();
+    service.process();
+    service.fail();
+
+    let out = service.service_metrics.to_prometheus();
+
+    assert!(out.contains("# TYPE ServiceMetrics_process_hit_count counter"));
+    assert!(out.contains("ServiceMetrics_process_hit_count_total 1"));
+    assert!(out.contains("# TYPE ServiceMetrics_fail_error_count counter"));
+    assert!(out.contains("ServiceMetrics_fail_error_count_total 1"));
+}